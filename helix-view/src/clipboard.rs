@@ -0,0 +1,275 @@
+use std::borrow::Cow;
+
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardType {
+    Clipboard,
+    Selection,
+}
+
+/// A provider of system clipboard access.
+///
+/// Implementations are free to support only a subset of clipboard
+/// functionality: `get_file_list` defaults to an error so providers that
+/// cannot enumerate file paths (most of them) don't need to implement it.
+pub trait ClipboardProvider: std::fmt::Debug {
+    fn name(&self) -> Cow<str>;
+    fn get_contents(&self, clipboard_type: ClipboardType) -> Result<String>;
+    fn set_contents(&mut self, contents: String, clipboard_type: ClipboardType) -> Result<()>;
+
+    /// Returns the file paths currently held on the clipboard, for clipboard
+    /// contents populated by a file manager (e.g. a "copy"/"cut" of one or
+    /// more files) rather than plain text.
+    fn get_file_list(&self, clipboard_type: ClipboardType) -> Result<Vec<String>> {
+        let _ = clipboard_type;
+        anyhow::bail!("{} does not support reading a file list", self.name())
+    }
+
+    /// Returns whether this provider can interact with `clipboard_type` at
+    /// all. Most platforms have no primary/selection clipboard distinct
+    /// from the system clipboard (macOS, Windows, Termux), so callers
+    /// should check `Selection` support before relying on it rather than
+    /// treating a failed read/write as a transient error.
+    fn supports(&self, clipboard_type: ClipboardType) -> bool {
+        let _ = clipboard_type;
+        true
+    }
+}
+
+#[derive(Debug)]
+struct NoClipboardProvider;
+
+impl ClipboardProvider for NoClipboardProvider {
+    fn name(&self) -> Cow<str> {
+        Cow::Borrowed("none")
+    }
+
+    fn get_contents(&self, _clipboard_type: ClipboardType) -> Result<String> {
+        anyhow::bail!("No clipboard provider found on this platform")
+    }
+
+    fn set_contents(&mut self, _contents: String, _clipboard_type: ClipboardType) -> Result<()> {
+        anyhow::bail!("No clipboard provider found on this platform")
+    }
+
+    fn supports(&self, _clipboard_type: ClipboardType) -> bool {
+        false
+    }
+}
+
+pub fn get_clipboard_provider() -> Box<dyn ClipboardProvider> {
+    provider::command_provider().unwrap_or_else(|| Box::new(NoClipboardProvider))
+}
+
+/// Clipboard providers that shell out to a platform clipboard utility.
+///
+/// This mirrors the approach taken by most terminal editors: there is no
+/// portable, dependency-free way to talk to X11/Wayland/macOS/Windows
+/// clipboards, so we probe `$PATH` and the session type for a utility we
+/// know how to drive.
+mod provider {
+    use std::{
+        borrow::Cow,
+        io::Write,
+        process::{Command, Stdio},
+    };
+
+    use anyhow::{Context as _, Result};
+
+    use super::{ClipboardProvider, ClipboardType};
+
+    #[derive(Debug, Clone)]
+    struct CommandConfig {
+        args: &'static [&'static str],
+        primary_args: Option<&'static [&'static str]>,
+    }
+
+    #[derive(Debug)]
+    pub struct CommandProvider {
+        name: &'static str,
+        get: CommandConfig,
+        set: CommandConfig,
+        /// `text/uri-list`-style listing, for file-manager copies.
+        get_file_list: Option<CommandConfig>,
+    }
+
+    impl CommandProvider {
+        fn run(&self, args: &[&str], stdin: Option<&str>) -> Result<String> {
+            let mut command = Command::new(args[0]);
+            command.args(&args[1..]).stdout(Stdio::piped());
+
+            if stdin.is_some() {
+                command.stdin(Stdio::piped());
+            }
+
+            let mut child = command
+                .spawn()
+                .with_context(|| format!("failed to start {}", args[0]))?;
+
+            if let Some(stdin) = stdin {
+                child
+                    .stdin
+                    .take()
+                    .expect("stdin was configured above")
+                    .write_all(stdin.as_bytes())?;
+            }
+
+            let output = child.wait_with_output()?;
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+
+        fn args_for(config: &CommandConfig, clipboard_type: ClipboardType) -> &'static [&'static str] {
+            match clipboard_type {
+                ClipboardType::Clipboard => config.args,
+                ClipboardType::Selection => config.primary_args.unwrap_or(config.args),
+            }
+        }
+    }
+
+    impl ClipboardProvider for CommandProvider {
+        fn name(&self) -> Cow<str> {
+            Cow::Borrowed(self.name)
+        }
+
+        fn get_contents(&self, clipboard_type: ClipboardType) -> Result<String> {
+            let args = Self::args_for(&self.get, clipboard_type);
+            self.run(args, None)
+        }
+
+        fn set_contents(&mut self, contents: String, clipboard_type: ClipboardType) -> Result<()> {
+            let args = Self::args_for(&self.set, clipboard_type);
+            self.run(args, Some(&contents))?;
+            Ok(())
+        }
+
+        fn get_file_list(&self, clipboard_type: ClipboardType) -> Result<Vec<String>> {
+            let Some(config) = &self.get_file_list else {
+                anyhow::bail!("{} does not support reading a file list", self.name);
+            };
+
+            let args = Self::args_for(config, clipboard_type);
+            let contents = self.run(args, None)?;
+            Ok(contents
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(uri_to_path)
+                .collect())
+        }
+
+        fn supports(&self, clipboard_type: ClipboardType) -> bool {
+            match clipboard_type {
+                ClipboardType::Clipboard => true,
+                ClipboardType::Selection => {
+                    self.get.primary_args.is_some() && self.set.primary_args.is_some()
+                }
+            }
+        }
+    }
+
+    fn is_exe_available(cmd: &str) -> bool {
+        which::which(cmd).is_ok()
+    }
+
+    /// Converts a `text/uri-list` entry (e.g. `file:///home/user/My%20File.txt`)
+    /// into a plain, percent-decoded filesystem path.
+    fn uri_to_path(uri: &str) -> String {
+        let uri = uri.strip_prefix("file://").unwrap_or(uri);
+        percent_decode(uri)
+    }
+
+    /// Decodes `%XX` percent-escapes in a URI path component.
+    fn percent_decode(s: &str) -> String {
+        let bytes = s.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'%' && i + 2 < bytes.len() {
+                let hex = std::str::from_utf8(&bytes[i + 1..=i + 2]).ok();
+                if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    out.push(byte);
+                    i += 3;
+                    continue;
+                }
+            }
+            out.push(bytes[i]);
+            i += 1;
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// Picks a clipboard command provider by probing the session for a
+    /// known utility. Wayland is preferred over X11 when `WAYLAND_DISPLAY`
+    /// is set, matching how most Wayland compositors run Xwayland in
+    /// parallel for compatibility.
+    pub fn command_provider() -> Option<Box<dyn ClipboardProvider>> {
+        if cfg!(target_os = "macos") && is_exe_available("pbcopy") && is_exe_available("pbpaste") {
+            return Some(Box::new(CommandProvider {
+                name: "pbcopy",
+                get: CommandConfig { args: &["pbpaste"], primary_args: None },
+                set: CommandConfig { args: &["pbcopy"], primary_args: None },
+                get_file_list: None,
+            }));
+        }
+
+        if std::env::var_os("WAYLAND_DISPLAY").is_some()
+            && is_exe_available("wl-copy")
+            && is_exe_available("wl-paste")
+        {
+            return Some(Box::new(CommandProvider {
+                name: "wl-clipboard",
+                get: CommandConfig {
+                    args: &["wl-paste", "--no-newline"],
+                    primary_args: Some(&["wl-paste", "--no-newline", "--primary"]),
+                },
+                set: CommandConfig {
+                    args: &["wl-copy"],
+                    primary_args: Some(&["wl-copy", "--primary"]),
+                },
+                get_file_list: Some(CommandConfig {
+                    args: &["wl-paste", "--no-newline", "--type", "text/uri-list"],
+                    primary_args: Some(&[
+                        "wl-paste",
+                        "--no-newline",
+                        "--primary",
+                        "--type",
+                        "text/uri-list",
+                    ]),
+                }),
+            }));
+        }
+
+        if std::env::var_os("DISPLAY").is_some() && is_exe_available("xclip") {
+            return Some(Box::new(CommandProvider {
+                name: "xclip",
+                get: CommandConfig {
+                    args: &["xclip", "-o", "-selection", "clipboard"],
+                    primary_args: Some(&["xclip", "-o", "-selection", "primary"]),
+                },
+                set: CommandConfig {
+                    args: &["xclip", "-i", "-selection", "clipboard"],
+                    primary_args: Some(&["xclip", "-i", "-selection", "primary"]),
+                },
+                get_file_list: Some(CommandConfig {
+                    args: &[
+                        "xclip", "-o", "-selection", "clipboard", "-t", "text/uri-list",
+                    ],
+                    primary_args: Some(&[
+                        "xclip", "-o", "-selection", "primary", "-t", "text/uri-list",
+                    ]),
+                }),
+            }));
+        }
+
+        if is_exe_available("termux-clipboard-get") && is_exe_available("termux-clipboard-set") {
+            return Some(Box::new(CommandProvider {
+                name: "termux",
+                get: CommandConfig { args: &["termux-clipboard-get"], primary_args: None },
+                set: CommandConfig { args: &["termux-clipboard-set"], primary_args: None },
+                get_file_list: None,
+            }));
+        }
+
+        None
+    }
+}
@@ -0,0 +1,44 @@
+// NOTE: this tree does not contain the rest of `Editor` (the document/view
+// tree that `current_ref!`/`doc!` operate on in register.rs, or the
+// search/command-line/insert-mode code that would call the setters below)
+// — only the fields register.rs needs are defined here. In the real crate
+// these belong on the existing `Editor` type alongside that state, not in
+// a type of their own, and the call sites that populate them still need to
+// be wired up at search/command-line completion and on leaving insert mode.
+
+/// State backing the read-only `/`, `:`, and `` ` `` registers (see
+/// [`crate::register::Registers::read`]). Each field holds whatever its
+/// setter below last stored, and stays `None` until that setter is called;
+/// nothing in this tree calls the setters.
+#[derive(Debug, Default)]
+pub struct Editor {
+    /// The search pattern last stored with `set_last_search_pattern`,
+    /// backing the `/` register.
+    pub last_search_pattern: Option<String>,
+    /// The command line last stored with `set_last_command_line`, backing
+    /// the `:` register.
+    pub last_command_line: Option<String>,
+    /// The inserted text last stored with `set_last_inserted_text`, backing
+    /// the `` ` `` register.
+    pub last_inserted_text: Option<String>,
+}
+
+impl Editor {
+    /// Records the pattern of a completed search, for the `/` register.
+    /// No search-execution path in this tree calls it.
+    pub fn set_last_search_pattern(&mut self, pattern: String) {
+        self.last_search_pattern = Some(pattern);
+    }
+
+    /// Records the text of an executed command line, for the `:` register.
+    /// No command-line execution path in this tree calls it.
+    pub fn set_last_command_line(&mut self, command: String) {
+        self.last_command_line = Some(command);
+    }
+
+    /// Records the text inserted during an insert-mode session, for the
+    /// `` ` `` register. No insert-mode-exit path in this tree calls it.
+    pub fn set_last_inserted_text(&mut self, text: String) {
+        self.last_inserted_text = Some(text);
+    }
+}
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, collections::HashMap, iter};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    iter,
+};
 
 use anyhow::Result;
 use helix_core::NATIVE_LINE_ENDING;
@@ -9,6 +13,17 @@ use crate::{
     Editor,
 };
 
+/// The shape of a register's content, used by paste commands to decide
+/// how to place it back into the document (e.g. opening a new line for
+/// linewise content instead of splicing it into the middle of one).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterShape {
+    #[default]
+    CharWise,
+    LineWise,
+    BlockWise,
+}
+
 /// A key-value store for saving sets of values.
 ///
 /// Each register corresponds to a `char`. Most chars can be used to store any set of
@@ -20,14 +35,34 @@ use crate::{
 /// * Selection contents (`.`)
 /// * Document path (`%`): filename of the current buffer
 /// * System clipboard (`*`)
-/// * Primary clipboard (`+`)
+/// * Primary clipboard (`+`): transparently falls back to the last
+///   internally-saved yank when the clipboard provider has no selection
+///   clipboard to read from or write to
+/// * Yank ring (`0`-`9`): `0` holds the most recent destructive yank, with
+///   each older yank shifting down into the next digit and falling off the
+///   end past `9`
+/// * Last search pattern (`/`)
+/// * Last command line (`:`)
+/// * Last inserted text (`` ` ``)
+/// * Clipboard file list (`~`): file paths from a file manager's copy/cut,
+///   one per line, when the clipboard provider can report them
+///
+/// Writing or pushing to an uppercase register (e.g. `A`) appends to the
+/// lowercase register of the same letter instead of replacing its contents,
+/// so that e.g. `"Ay` collects multiple yanks into register `a`. The
+/// register keeps the shape of its existing content across such appends,
+/// rather than taking on the shape of whatever was most recently appended.
 #[derive(Debug)]
 pub struct Registers {
-    /// The mapping of register to values.
+    /// The mapping of register to its content shape and values.
     /// Values are stored in reverse order when inserted with `Registers::write`.
     /// The order is reversed again in `Registers::read`. This allows us to
     /// efficiently prepend new values in `Registers::push`.
-    inner: HashMap<char, Vec<String>>,
+    inner: HashMap<char, (RegisterShape, Vec<String>)>,
+    /// The yank ring backing the numbered registers `0`-`9`. `ring[0]` is
+    /// register `0`, `ring[1]` is register `1`, and so on; `push_ring`
+    /// maintains the shift-and-drop behavior described above.
+    ring: VecDeque<Vec<String>>,
     clipboard_provider: Box<dyn ClipboardProvider>,
 }
 
@@ -35,11 +70,15 @@ impl Default for Registers {
     fn default() -> Self {
         Self {
             inner: Default::default(),
+            ring: Default::default(),
             clipboard_provider: get_clipboard_provider(),
         }
     }
 }
 
+/// The number of numbered registers (`0`-`9`) retained in the yank ring.
+const RING_SIZE: usize = 10;
+
 // Some special registers must allocate their values while others and regular
 // registers can hand out borrowed values.
 type RegisterValues<'a> = Box<dyn ExactSizeIterator<Item = Cow<'a, str>> + 'a>;
@@ -73,51 +112,180 @@ impl Registers {
 
                 Some(Box::new(iter::once(path)))
             }
-            '*' | '+' => Some(read_from_clipboard(
-                self.clipboard_provider.as_ref(),
-                self.inner.get(&name),
-                match name {
+            '*' | '+' => {
+                let clipboard_type = match name {
                     '*' => ClipboardType::Clipboard,
                     '+' => ClipboardType::Selection,
                     _ => unreachable!(),
-                },
-            )),
+                };
+
+                if clipboard_type == ClipboardType::Selection
+                    && !self.clipboard_provider.supports(clipboard_type)
+                {
+                    // No primary/selection clipboard on this platform or
+                    // provider: fall back to whatever was last yanked into
+                    // it instead of silently yielding nothing.
+                    return self.inner.get(&name).map(|(_, values)| {
+                        Box::new(values.iter().map(Cow::from).rev()) as RegisterValues
+                    });
+                }
+
+                Some(read_from_clipboard(
+                    self.clipboard_provider.as_ref(),
+                    self.inner.get(&name).map(|(_, values)| values),
+                    clipboard_type,
+                ))
+            }
+            '/' => editor
+                .last_search_pattern
+                .as_deref()
+                .map(|pattern| Box::new(iter::once(Cow::Borrowed(pattern))) as RegisterValues),
+            ':' => editor
+                .last_command_line
+                .as_deref()
+                .map(|command| Box::new(iter::once(Cow::Borrowed(command))) as RegisterValues),
+            '`' => editor
+                .last_inserted_text
+                .as_deref()
+                .map(|text| Box::new(iter::once(Cow::Borrowed(text))) as RegisterValues),
+            '~' => {
+                let paths = self
+                    .clipboard_provider
+                    .get_file_list(ClipboardType::Clipboard)
+                    .ok()?;
+                Some(Box::new(paths.into_iter().map(Cow::Owned).collect::<Vec<_>>().into_iter())
+                    as RegisterValues)
+            }
+            '0'..='9' => {
+                let index = name as usize - '0' as usize;
+                self.ring
+                    .get(index)
+                    .map(|values| Box::new(values.iter().map(Cow::from).rev()) as RegisterValues)
+            }
+            _ if name.is_ascii_uppercase() => self.read(name.to_ascii_lowercase(), editor),
             _ => self
                 .inner
                 .get(&name)
-                .map(|values| Box::new(values.iter().map(Cow::from).rev()) as RegisterValues),
+                .map(|(_, values)| Box::new(values.iter().map(Cow::from).rev()) as RegisterValues),
         }
     }
 
-    pub fn write(&mut self, name: char, mut values: Vec<String>) -> Result<()> {
+    /// Returns the content shape of `name`, if it has been written to.
+    ///
+    /// For the clipboard registers (`*`/`+`), the shape is inferred from the
+    /// live clipboard contents rather than stored: if the round-tripped
+    /// contents end in the platform's native line ending, the register is
+    /// treated as linewise.
+    pub fn shape(&self, name: char) -> Option<RegisterShape> {
+        match name {
+            '*' | '+' => {
+                let clipboard_type = match name {
+                    '*' => ClipboardType::Clipboard,
+                    '+' => ClipboardType::Selection,
+                    _ => unreachable!(),
+                };
+
+                if clipboard_type == ClipboardType::Selection
+                    && !self.clipboard_provider.supports(clipboard_type)
+                {
+                    return self.inner.get(&name).map(|(shape, _)| *shape);
+                }
+
+                let contents = self.clipboard_provider.get_contents(clipboard_type).ok()?;
+                Some(if contents.ends_with(NATIVE_LINE_ENDING.as_str()) {
+                    RegisterShape::LineWise
+                } else {
+                    RegisterShape::CharWise
+                })
+            }
+            _ if name.is_ascii_uppercase() => self.shape(name.to_ascii_lowercase()),
+            _ => self.inner.get(&name).map(|(shape, _)| *shape),
+        }
+    }
+
+    /// Pushes a new set of destructive-yank values onto the front of the
+    /// yank ring, shifting the contents of register `0` into `1`, `1` into
+    /// `2`, and so on. Values past register `9` are dropped.
+    pub fn push_ring(&mut self, mut values: Vec<String>) {
+        values.reverse();
+        self.ring.push_front(values);
+        self.ring.truncate(RING_SIZE);
+    }
+
+    pub fn write(&mut self, name: char, values: Vec<String>) -> Result<()> {
+        self.write_with_shape(name, values, RegisterShape::CharWise)
+    }
+
+    pub fn write_with_shape(
+        &mut self,
+        name: char,
+        mut values: Vec<String>,
+        shape: RegisterShape,
+    ) -> Result<()> {
         match name {
             '_' => Ok(()),
-            '#' | '.' | '%' => Err(anyhow::anyhow!("Register {name} does not support writing")),
+            '#' | '.' | '%' | '/' | ':' | '`' | '~' => {
+                Err(anyhow::anyhow!("Register {name} does not support writing"))
+            }
+            '0'..='9' => Err(anyhow::anyhow!("Register {name} does not support writing")),
             '*' | '+' => {
-                self.clipboard_provider.set_contents(
-                    values.join(NATIVE_LINE_ENDING.as_str()),
-                    match name {
-                        '*' => ClipboardType::Clipboard,
-                        '+' => ClipboardType::Selection,
-                        _ => unreachable!(),
-                    },
-                )?;
+                let clipboard_type = match name {
+                    '*' => ClipboardType::Clipboard,
+                    '+' => ClipboardType::Selection,
+                    _ => unreachable!(),
+                };
+
+                // If there's no primary selection clipboard on this
+                // platform or provider, keep the values around internally
+                // instead of failing the write outright. The system
+                // clipboard ('*') still surfaces a real error if writing to
+                // it fails, since there's no sensible fallback for it.
+                if clipboard_type != ClipboardType::Selection
+                    || self.clipboard_provider.supports(clipboard_type)
+                {
+                    self.clipboard_provider
+                        .set_contents(values.join(NATIVE_LINE_ENDING.as_str()), clipboard_type)?;
+                }
                 values.reverse();
-                self.inner.insert(name, values);
+                self.inner.insert(name, (shape, values));
+                Ok(())
+            }
+            _ if name.is_ascii_uppercase() => {
+                let name = name.to_ascii_lowercase();
+                values.reverse();
+                // Appending keeps the shape of the register's existing
+                // content rather than the shape of the newly appended
+                // values, so e.g. a linewise yank into `a` followed by a
+                // charwise `"Ay` stays linewise for paste placement.
+                let shape = match self.inner.remove(&name) {
+                    Some((existing_shape, existing)) => {
+                        values.extend(existing);
+                        existing_shape
+                    }
+                    None => shape,
+                };
+                self.inner.insert(name, (shape, values));
                 Ok(())
             }
             _ => {
                 values.reverse();
-                self.inner.insert(name, values);
+                self.inner.insert(name, (shape, values));
                 Ok(())
             }
         }
     }
 
     pub fn push(&mut self, name: char, value: String) -> Result<()> {
+        self.push_with_shape(name, value, RegisterShape::CharWise)
+    }
+
+    pub fn push_with_shape(&mut self, name: char, value: String, shape: RegisterShape) -> Result<()> {
         match name {
             '_' => Ok(()),
-            '#' | '.' | '%' => Err(anyhow::anyhow!("Register {name} does not support pushing")),
+            '#' | '.' | '%' | '/' | ':' | '`' | '~' => {
+                Err(anyhow::anyhow!("Register {name} does not support pushing"))
+            }
+            '0'..='9' => Err(anyhow::anyhow!("Register {name} does not support pushing")),
             '*' | '+' => {
                 let clipboard_type = match name {
                     '*' => ClipboardType::Clipboard,
@@ -125,9 +293,18 @@ impl Registers {
                     _ => unreachable!(),
                 };
 
+                if clipboard_type == ClipboardType::Selection
+                    && !self.clipboard_provider.supports(clipboard_type)
+                {
+                    let entry = self.inner.entry(name).or_insert_with(|| (shape, Vec::new()));
+                    entry.0 = shape;
+                    entry.1.push(value);
+                    return Ok(());
+                }
+
                 let mut values: Vec<_> = read_from_clipboard(
                     self.clipboard_provider.as_ref(),
-                    self.inner.get(&name),
+                    self.inner.get(&name).map(|(_, values)| values),
                     clipboard_type,
                 )
                 .map(|value| value.to_string())
@@ -138,12 +315,29 @@ impl Registers {
                 self.clipboard_provider
                     .set_contents(values.join(NATIVE_LINE_ENDING.as_str()), clipboard_type)?;
                 values.reverse();
-                self.inner.insert(name, values);
+                self.inner.insert(name, (shape, values));
 
                 Ok(())
             }
+            _ if name.is_ascii_uppercase() => {
+                let name = name.to_ascii_lowercase();
+                // Keep the existing register's shape rather than overwriting
+                // it with `shape` on every push, for the same reason
+                // `write_with_shape`'s uppercase branch keeps the shape of
+                // the existing content: appending shouldn't retroactively
+                // change how the whole register pastes.
+                let entry = self.inner.entry(name).or_insert_with(|| (shape, Vec::new()));
+                // Same tail-push accumulation as the lowercase branch below,
+                // just redirected to the lowercase register: a sequence of
+                // pushes to `A` must read back in the same order as the same
+                // sequence of pushes to `a` would.
+                entry.1.push(value);
+                Ok(())
+            }
             _ => {
-                self.inner.entry(name).or_insert_with(Vec::new).push(value);
+                let entry = self.inner.entry(name).or_insert_with(|| (shape, Vec::new()));
+                entry.0 = shape;
+                entry.1.push(value);
                 Ok(())
             }
         }
@@ -161,7 +355,7 @@ impl Registers {
         self.inner
             .iter()
             .filter(|(name, _)| !matches!(name, '*' | '+'))
-            .map(|(name, values)| {
+            .map(|(name, (_, values))| {
                 let preview = values
                     .last()
                     .and_then(|s| s.lines().next())
@@ -169,6 +363,14 @@ impl Registers {
 
                 (*name, preview)
             })
+            .chain(self.ring.iter().enumerate().map(|(index, values)| {
+                let preview = values
+                    .last()
+                    .and_then(|s| s.lines().next())
+                    .unwrap_or("<empty>");
+
+                ((b'0' + index as u8) as char, preview)
+            }))
             .chain(
                 [
                     ('_', "<empty>"),
@@ -176,7 +378,18 @@ impl Registers {
                     ('.', "<selection contents>"),
                     ('%', "<document path>"),
                     ('*', "<system clipboard>"),
-                    ('+', "<primary clipboard>"),
+                    (
+                        '+',
+                        if self.clipboard_provider.supports(ClipboardType::Selection) {
+                            "<primary clipboard>"
+                        } else {
+                            "<primary clipboard — unavailable>"
+                        },
+                    ),
+                    ('/', "<last search>"),
+                    (':', "<last command>"),
+                    ('`', "<last insert>"),
+                    ('~', "<clipboard files>"),
                 ]
                 .iter()
                 .copied(),
@@ -184,12 +397,14 @@ impl Registers {
     }
 
     pub fn clear(&mut self) {
-        self.inner.clear()
+        self.inner.clear();
+        self.ring.clear();
     }
 
     pub fn remove(&mut self, name: char) -> bool {
         match name {
-            '_' | '#' | '.' | '%' | '*' | '+' => false,
+            '_' | '#' | '.' | '%' | '*' | '+' | '/' | ':' | '`' | '~' | '0'..='9' => false,
+            _ if name.is_ascii_uppercase() => self.remove(name.to_ascii_lowercase()),
             _ => self.inner.remove(&name).is_some(),
         }
     }